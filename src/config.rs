@@ -0,0 +1,96 @@
+/// Voice activity detection settings passed through to faster-whisper's
+/// built-in VAD filter.
+#[derive(Clone, Debug)]
+pub struct VadConfig {
+    pub active: bool,
+    pub threshold: f32,
+    pub min_speech_duration: f32,
+    pub max_speech_duration: Option<f32>,
+    pub min_silence_duration: f32,
+    pub padding_duration: f32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            active: false,
+            threshold: 0.5,
+            min_speech_duration: 0.0,
+            max_speech_duration: None,
+            min_silence_duration: 2.0,
+            padding_duration: 0.4,
+        }
+    }
+}
+
+/// Decoding and runtime configuration shared by `WhisperModel` and
+/// `WhisperTranscriber`.
+#[derive(Clone, Debug)]
+pub struct WhisperConfig {
+    pub starting_prompt: Option<String>,
+    pub prefix: Option<String>,
+    pub language: Option<String>,
+    pub beam_size: i32,
+    pub best_of: i32,
+    pub patience: f32,
+    pub length_penalty: f32,
+    pub chunk_length: Option<i32>,
+    pub vad: VadConfig,
+    /// When set, `transcribe` populates `Segment::words` with per-word
+    /// timing and confidence, at the cost of a slower decode.
+    pub word_timestamps: bool,
+    /// How often `transcribe_stream` re-transcribes the buffered audio, in
+    /// seconds. Smaller values commit words sooner at the cost of more
+    /// Python round-trips.
+    pub parse_interval: f32,
+    /// Safety margin, in seconds, kept before the last committed word when
+    /// trimming the stream buffer, so the next pass still has a little
+    /// context leading into unconfirmed audio.
+    pub buffer_trim_padding: f32,
+    /// Temperatures tried in order during decoding. Whenever a segment's
+    /// `compression_ratio` exceeds `compression_ratio_threshold` or its
+    /// `avg_logprob` falls below `log_prob_threshold`, faster-whisper
+    /// discards that segment and retries at the next temperature, since a
+    /// single greedy (temperature 0.0) decode often produces hallucinated or
+    /// repetitive output. The temperature that finally won is reported back
+    /// in `Segment::temperature`.
+    pub temperatures: Vec<f32>,
+    /// Above this compression ratio a segment is considered a failed decode
+    /// (likely repetition) and re-decoded at the next temperature.
+    pub compression_ratio_threshold: f32,
+    /// Below this average log probability a segment is considered a failed
+    /// decode (low confidence) and re-decoded at the next temperature.
+    pub log_prob_threshold: f32,
+    /// Above this no-speech probability a segment is treated as silence and
+    /// the temperature fallback is skipped for it.
+    pub no_speech_threshold: f32,
+    /// Number of chunks decoded together by `transcribe_batch`'s
+    /// `BatchedInferencePipeline`. Higher values improve GPU throughput on
+    /// bulk jobs at the cost of more memory; ignored when the installed
+    /// faster-whisper falls back to sequential decoding.
+    pub batch_size: i32,
+}
+
+impl Default for WhisperConfig {
+    fn default() -> Self {
+        Self {
+            starting_prompt: None,
+            prefix: None,
+            language: None,
+            beam_size: 5,
+            best_of: 5,
+            patience: 1.0,
+            length_penalty: 1.0,
+            chunk_length: None,
+            vad: VadConfig::default(),
+            word_timestamps: false,
+            parse_interval: 1.0,
+            buffer_trim_padding: 0.1,
+            temperatures: vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0],
+            compression_ratio_threshold: 2.4,
+            log_prob_threshold: -1.0,
+            no_speech_threshold: 0.6,
+            batch_size: 8,
+        }
+    }
+}
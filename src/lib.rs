@@ -2,12 +2,121 @@ pub mod config;
 pub mod pyscripts;
 
 use config::*;
+use num_complex::Complex32;
 use pyo3::ffi::c_str;
 use pyo3::{prelude::*, types::PyModule};
 use pyscripts::*;
+use realfft::RealFftPlanner;
 use std::ffi::CString;
+use std::f32::consts::PI;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
 use std::{error::Error, fmt::Debug, i32};
 
+/// Sample rate faster-whisper expects for raw PCM input.
+const SAMPLE_RATE: u32 = 16_000;
+
+/// Block size used by `resample_to_16k`'s FFT resampler. Large enough to
+/// keep spectral resolution reasonable, small enough to keep each FFT cheap.
+const RESAMPLE_BLOCK_SIZE: usize = 8192;
+
+/// Hop between analysis blocks in `resample_to_16k` — half the block size,
+/// so consecutive Hann-windowed blocks overlap 50%. At that overlap a Hann
+/// window is constant-overlap-add (COLA): two neighboring shifted windows
+/// sum to exactly 1, so summing the (resampled) blocks back together
+/// reconstructs the signal with no extra normalization, away from the first
+/// and last half block.
+const RESAMPLE_HOP: usize = RESAMPLE_BLOCK_SIZE / 2;
+
+/// A length-`len` Hann window, used to taper each analysis block in
+/// `resample_to_16k` before its FFT so that overlap-adding the resampled
+/// blocks back together doesn't leave a click at every block boundary the
+/// way summing independently-transformed rectangular blocks would.
+fn hann_window(len: usize) -> Vec<f32> {
+    if len <= 1 {
+        return vec![1.0; len];
+    }
+    (0..len)
+        .map(|n| 0.5 - 0.5 * (2.0 * PI * n as f32 / len as f32).cos())
+        .collect()
+}
+
+/// Resamples `samples` from `sample_rate` to `SAMPLE_RATE` using windowed
+/// overlap-add FFT resampling: the signal is split into 50%-overlapping,
+/// Hann-windowed blocks; each block is forward-transformed, its spectrum is
+/// truncated (downsampling) or zero-padded (upsampling) to match the
+/// target/source length ratio, inverse-transformed back to the time domain,
+/// and summed into the output at the correspondingly scaled hop. Overlap-add
+/// is what makes this clean and band-limited without the clicks a naive
+/// scheme of resampling non-overlapping blocks independently and
+/// concatenating them would leave at every block boundary.
+fn resample_to_16k(samples: &[f32], sample_rate: u32) -> Result<Vec<f32>, Box<dyn Error>> {
+    if sample_rate == SAMPLE_RATE || samples.is_empty() {
+        return Ok(samples.to_vec());
+    }
+
+    let ratio = SAMPLE_RATE as f64 / sample_rate as f64;
+    let window = hann_window(RESAMPLE_BLOCK_SIZE);
+    let out_block_len = ((RESAMPLE_BLOCK_SIZE as f64) * ratio).round().max(1.0) as usize;
+    let out_len = (samples.len() as f64 * ratio).ceil() as usize;
+
+    let mut planner = RealFftPlanner::<f32>::new();
+    let forward = planner.plan_fft_forward(RESAMPLE_BLOCK_SIZE);
+    let inverse = planner.plan_fft_inverse(out_block_len);
+    let out_bins = out_block_len / 2 + 1;
+    // realfft's inverse transform is unnormalized, so scale back down by the
+    // size of the *input* block each spectrum was derived from.
+    let norm = 1.0 / RESAMPLE_BLOCK_SIZE as f32;
+
+    let mut output = vec![0.0f32; out_len + out_block_len];
+
+    let mut pos = 0;
+    while pos < samples.len() {
+        let end = (pos + RESAMPLE_BLOCK_SIZE).min(samples.len());
+
+        let mut time_in = forward.make_input_vec();
+        for (i, sample) in samples[pos..end].iter().enumerate() {
+            time_in[i] = sample * window[i];
+        }
+        let mut freq = forward.make_output_vec();
+        let mut scratch = forward.make_scratch_vec();
+        forward.process_with_scratch(&mut time_in, &mut freq, &mut scratch)?;
+
+        let mut resized_freq = vec![Complex32::new(0.0, 0.0); out_bins];
+        let copy_bins = freq.len().min(out_bins);
+        resized_freq[..copy_bins].copy_from_slice(&freq[..copy_bins]);
+
+        let mut time_out = inverse.make_output_vec();
+        let mut scratch = inverse.make_scratch_vec();
+        inverse.process_with_scratch(&mut resized_freq, &mut time_out, &mut scratch)?;
+
+        let out_pos = (pos as f64 * ratio).round() as usize;
+        for (i, sample) in time_out.into_iter().enumerate() {
+            if let Some(slot) = output.get_mut(out_pos + i) {
+                *slot += sample * norm;
+            }
+        }
+
+        pos += RESAMPLE_HOP;
+    }
+
+    output.truncate(out_len);
+    Ok(output)
+}
+
+/// A single word from a streaming hypothesis pass, local to whatever buffer
+/// produced it. Not exposed publicly; `transcribe_stream` only ever hands
+/// callers finalized `Segment`s.
+#[derive(Clone, Debug, PartialEq)]
+struct StreamWord {
+    word: String,
+    start: f32,
+    end: f32,
+    probability: f32,
+}
+
 #[derive(Debug)]
 pub struct WhisperModel {
     module: Py<PyModule>,
@@ -26,6 +135,19 @@ pub struct Segment {
     pub avg_logprob: f32,
     pub compression_ratio: f32,
     pub no_speech_prob: f32,
+    /// Per-word timing and confidence. Empty unless
+    /// `WhisperConfig::word_timestamps` was set for the call that produced
+    /// this segment; segments from `transcribe_stream` always carry their
+    /// words.
+    pub words: Vec<Word>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Word {
+    pub start: f32,
+    pub end: f32,
+    pub word: String,
+    pub probability: f32,
 }
 
 #[derive(Clone)]
@@ -51,6 +173,185 @@ impl Debug for Segments {
         f.write_str(&self.0)
     }
 }
+
+impl Segments {
+    /// Renders the segments as SubRip (`.srt`) subtitles.
+    pub fn to_srt(&self) -> String {
+        let mut out = String::new();
+        for (index, segment) in self.1.iter().enumerate() {
+            out.push_str(&format!(
+                "{}\n{} --> {}\n{}\n\n",
+                index + 1,
+                format_timestamp_srt(segment.start),
+                format_timestamp_srt(segment.end),
+                segment.text.trim()
+            ));
+        }
+        out
+    }
+
+    /// Renders the segments as WebVTT (`.vtt`) subtitles. Segments carrying
+    /// word-level timestamps are split into one cue per word instead of one
+    /// per segment, so players can highlight words as they're spoken.
+    pub fn to_vtt(&self) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+        for segment in &self.1 {
+            if segment.words.is_empty() {
+                out.push_str(&format!(
+                    "{} --> {}\n{}\n\n",
+                    format_timestamp_vtt(segment.start),
+                    format_timestamp_vtt(segment.end),
+                    segment.text.trim()
+                ));
+            } else {
+                for word in &segment.words {
+                    out.push_str(&format!(
+                        "{} --> {}\n{}\n\n",
+                        format_timestamp_vtt(word.start),
+                        format_timestamp_vtt(word.end),
+                        word.word.trim()
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    /// Renders the segments as CSV, one row per segment.
+    pub fn to_csv(&self) -> String {
+        let mut out =
+            String::from("id,seek,start,end,text,avg_logprob,compression_ratio,no_speech_prob\n");
+        for segment in &self.1 {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                segment.id,
+                segment.seek,
+                csv_float(segment.start),
+                csv_float(segment.end),
+                csv_escape(&segment.text),
+                csv_float(segment.avg_logprob),
+                csv_float(segment.compression_ratio),
+                csv_float(segment.no_speech_prob),
+            ));
+        }
+        out
+    }
+
+    /// Renders the segments as a JSON array, including per-word timing when
+    /// present.
+    pub fn to_json(&self) -> String {
+        let segments: Vec<String> = self
+            .1
+            .iter()
+            .map(|segment| {
+                let words: Vec<String> = segment
+                    .words
+                    .iter()
+                    .map(|word| {
+                        format!(
+                            r#"{{"start":{},"end":{},"word":"{}","probability":{}}}"#,
+                            json_float(word.start),
+                            json_float(word.end),
+                            json_escape(&word.word),
+                            json_float(word.probability)
+                        )
+                    })
+                    .collect();
+
+                format!(
+                    r#"{{"id":{},"seek":{},"start":{},"end":{},"text":"{}","temperature":{},"avg_logprob":{},"compression_ratio":{},"no_speech_prob":{},"words":[{}]}}"#,
+                    segment.id,
+                    segment.seek,
+                    json_float(segment.start),
+                    json_float(segment.end),
+                    json_escape(&segment.text),
+                    json_float(segment.temperature),
+                    json_float(segment.avg_logprob),
+                    json_float(segment.compression_ratio),
+                    json_float(segment.no_speech_prob),
+                    words.join(",")
+                )
+            })
+            .collect();
+
+        format!("[{}]", segments.join(","))
+    }
+}
+
+/// Formats seconds as SRT's `HH:MM:SS,mmm` timestamp.
+fn format_timestamp_srt(seconds: f32) -> String {
+    format_timestamp(seconds, ',')
+}
+
+/// Formats seconds as WebVTT's `HH:MM:SS.mmm` timestamp.
+fn format_timestamp_vtt(seconds: f32) -> String {
+    format_timestamp(seconds, '.')
+}
+
+fn format_timestamp(seconds: f32, decimal_separator: char) -> String {
+    let total_millis = (seconds.max(0.0) * 1000.0).round() as i64;
+    let hours = total_millis / 3_600_000;
+    let minutes = (total_millis / 60_000) % 60;
+    let secs = (total_millis / 1000) % 60;
+    let millis = total_millis % 1000;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        hours, minutes, secs, decimal_separator, millis
+    )
+}
+
+/// Quotes a CSV field if it contains a comma, quote, or newline, doubling any
+/// embedded quotes per RFC 4180.
+fn csv_escape(field: &str) -> String {
+    if field.contains(['"', ',', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escapes a string for embedding in a JSON string literal.
+fn json_escape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Renders a decoder-derived float for JSON, substituting `null` for
+/// non-finite values since JSON has no NaN/Infinity literal and any
+/// decoder-derived field — `start`/`end` timestamps included, not just
+/// confidence fields like `avg_logprob`, `no_speech_prob`, word
+/// `probability`, or `temperature` — can occasionally come back non-finite.
+fn json_float(value: f32) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Renders a decoder-derived float for CSV, leaving the field blank for
+/// non-finite values since CSV has no standard NaN/Infinity representation.
+/// Applies to `start`/`end` timestamps as well as confidence fields —
+/// any of them can occasionally come back non-finite.
+fn csv_float(value: f32) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::new()
+    }
+}
+
 //Transcription
 impl WhisperTranscriber {
     /// Creates a new WhisperTranscriber with the given configuration
@@ -104,12 +405,30 @@ impl WhisperTranscriber {
                 self.config.length_penalty,
                 Self::convert(self.config.chunk_length.clone()),
                 vad,
+                self.config.word_timestamps,
+                (
+                    self.config.temperatures.clone(),
+                    self.config.compression_ratio_threshold,
+                    self.config.log_prob_threshold,
+                    self.config.no_speech_threshold,
+                ),
             );
 
             let pysegments = activators
                 .getattr("transcribe_audio")?
                 .call1(transcribe_args)?
-                .extract::<Vec<(i32, i32, f32, f32, String, f32, f32, f32, f32)>>()?;
+                .extract::<Vec<(
+                    i32,
+                    i32,
+                    f32,
+                    f32,
+                    String,
+                    f32,
+                    f32,
+                    f32,
+                    f32,
+                    Vec<(f32, f32, String, f32)>,
+                )>>()?;
             let mut segments = Vec::with_capacity(pysegments.len());
 
             for segment in pysegments {
@@ -123,6 +442,16 @@ impl WhisperTranscriber {
                     avg_logprob: segment.6,
                     compression_ratio: segment.7,
                     no_speech_prob: segment.8,
+                    words: segment
+                        .9
+                        .into_iter()
+                        .map(|(start, end, word, probability)| Word {
+                            start,
+                            end,
+                            word,
+                            probability,
+                        })
+                        .collect(),
                 });
             }
 
@@ -137,6 +466,449 @@ impl WhisperTranscriber {
         Ok(Segments(text, segments))
     }
 
+    /// Transcribes several files in one `Python::attach` session, loading the
+    /// model only once and running them through faster-whisper's
+    /// `BatchedInferencePipeline` (amortizing model load and GPU transfer
+    /// across the whole batch) instead of the single-file decode `transcribe`
+    /// re-enters Python for on every call. Falls back to sequential decoding
+    /// on the plain model when the installed faster-whisper doesn't have the
+    /// batched pipeline. Results are returned in the same order as `paths`.
+    pub fn transcribe_batch(&self, paths: Vec<String>) -> Result<Vec<Segments>, Box<dyn Error>> {
+        let script_code = get_script();
+
+        Python::attach(|py| {
+            let activators = PyModule::from_code(
+                py,
+                CString::new(script_code).unwrap().as_c_str(),
+                c_str!("whisper.py"),
+                c_str!("Whisper"),
+            )
+            .expect("should have activators");
+
+            let args = (
+                self.model.clone(),
+                self.device.clone(),
+                self.compute_type.clone(),
+            );
+            let model = activators.getattr("new_model")?.call1(args)?;
+
+            let vad = (
+                self.config.vad.active,
+                self.config.vad.threshold,
+                self.config.vad.min_speech_duration,
+                Self::convert(self.config.vad.max_speech_duration),
+                self.config.vad.min_silence_duration,
+                self.config.vad.padding_duration,
+            );
+
+            let batch_args = (
+                model,
+                paths,
+                Self::convert(self.config.starting_prompt.clone()),
+                Self::convert(self.config.prefix.clone()),
+                Self::convert(self.config.language.clone()),
+                self.config.beam_size,
+                self.config.best_of,
+                self.config.patience,
+                self.config.length_penalty,
+                Self::convert(self.config.chunk_length.clone()),
+                vad,
+                self.config.word_timestamps,
+                (
+                    self.config.temperatures.clone(),
+                    self.config.compression_ratio_threshold,
+                    self.config.log_prob_threshold,
+                    self.config.no_speech_threshold,
+                ),
+                self.config.batch_size,
+            );
+
+            let pyresults = activators
+                .getattr("transcribe_batch")?
+                .call1(batch_args)?
+                .extract::<Vec<
+                    Vec<(
+                        i32,
+                        i32,
+                        f32,
+                        f32,
+                        String,
+                        f32,
+                        f32,
+                        f32,
+                        f32,
+                        Vec<(f32, f32, String, f32)>,
+                    )>,
+                >>()?;
+
+            let mut results = Vec::with_capacity(pyresults.len());
+
+            for pysegments in pyresults {
+                let mut segments = Vec::with_capacity(pysegments.len());
+                for segment in pysegments {
+                    segments.push(Segment {
+                        id: segment.0,
+                        seek: segment.1,
+                        start: segment.2,
+                        end: segment.3,
+                        text: segment.4,
+                        temperature: segment.5,
+                        avg_logprob: segment.6,
+                        compression_ratio: segment.7,
+                        no_speech_prob: segment.8,
+                        words: segment
+                            .9
+                            .into_iter()
+                            .map(|(start, end, word, probability)| Word {
+                                start,
+                                end,
+                                word,
+                                probability,
+                            })
+                            .collect(),
+                    });
+                }
+
+                let mut text = String::new();
+                for segment in &segments {
+                    text.push_str(&segment.text);
+                }
+                results.push(Segments(text, segments));
+            }
+
+            Ok::<Vec<Segments>, Box<dyn Error>>(results)
+        })
+    }
+
+    /// Streams segments one at a time as faster-whisper decodes them,
+    /// instead of materializing the whole file's output before returning.
+    /// `on_segment` is invoked, with the GIL released, after each segment is
+    /// decoded; setting `cancel` stops pulling further segments from the
+    /// underlying generator, aborting the rest of the decode. Gives UIs live
+    /// partial output and a way to abort a runaway job on a large file.
+    pub fn transcribe_with_progress<F>(
+        &self,
+        path: String,
+        cancel: Arc<AtomicBool>,
+        mut on_segment: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&Segment) + Send,
+    {
+        let script_code = get_script();
+
+        Python::attach(|py| {
+            let activators = PyModule::from_code(
+                py,
+                CString::new(script_code).unwrap().as_c_str(),
+                c_str!("whisper.py"),
+                c_str!("Whisper"),
+            )
+            .expect("should have activators");
+
+            let args = (
+                self.model.clone(),
+                self.device.clone(),
+                self.compute_type.clone(),
+            );
+            let model = activators.getattr("new_model")?.call1(args)?;
+
+            let vad = (
+                self.config.vad.active,
+                self.config.vad.threshold,
+                self.config.vad.min_speech_duration,
+                Self::convert(self.config.vad.max_speech_duration),
+                self.config.vad.min_silence_duration,
+                self.config.vad.padding_duration,
+            );
+
+            let transcribe_args = (
+                model,
+                path,
+                Self::convert(self.config.starting_prompt.clone()),
+                Self::convert(self.config.prefix.clone()),
+                Self::convert(self.config.language.clone()),
+                self.config.beam_size,
+                self.config.best_of,
+                self.config.patience,
+                self.config.length_penalty,
+                Self::convert(self.config.chunk_length.clone()),
+                vad,
+                self.config.word_timestamps,
+                (
+                    self.config.temperatures.clone(),
+                    self.config.compression_ratio_threshold,
+                    self.config.log_prob_threshold,
+                    self.config.no_speech_threshold,
+                ),
+            );
+
+            let generator = activators
+                .getattr("transcribe_progress")?
+                .call1(transcribe_args)?;
+            let mut generator = generator.try_iter()?;
+
+            while !cancel.load(Ordering::Relaxed) {
+                let Some(item) = generator.next() else {
+                    break;
+                };
+
+                let segment = progress_tuple_to_segment(item?.extract()?);
+                py.allow_threads(|| on_segment(&segment));
+            }
+
+            Ok::<(), Box<dyn Error>>(())
+        })
+    }
+
+    /// Transcribes an in-memory mono PCM buffer, resampling it to 16 kHz
+    /// first if `sample_rate` doesn't already match what faster-whisper
+    /// expects. Lets callers feed audio they already hold in memory (decoded
+    /// WAV, microphone frames, network streams) without a temp file of their
+    /// own.
+    pub fn transcribe_samples(
+        &self,
+        samples: Vec<f32>,
+        sample_rate: u32,
+    ) -> Result<Segments, Box<dyn Error>> {
+        let samples = resample_to_16k(&samples, sample_rate)?;
+        let path = write_temp_wav(&samples)?;
+        let result = self.transcribe(path.to_string_lossy().to_string());
+        let _ = std::fs::remove_file(&path);
+        result
+    }
+
+    /// Streams transcription over a growing audio source, yielding stabilized
+    /// `Segment`s as the LocalAgreement-2 policy confirms them instead of
+    /// blocking until the whole recording is done.
+    ///
+    /// `audio_rx` delivers mono 16 kHz PCM chunks as they arrive (from a
+    /// microphone, a channel fed by a file being appended to, etc). Every
+    /// `config.parse_interval` seconds of newly arrived audio, the entire
+    /// buffered-so-far audio is re-transcribed with word-level timestamps;
+    /// the longest run of words that agrees with the previous pass is
+    /// committed, handed to `on_segment`, and trimmed from the buffer so the
+    /// next pass only has to redecode unconfirmed audio. The model is loaded
+    /// once and kept alive for the whole stream in a single `Python::attach`
+    /// session.
+    pub fn transcribe_stream<F>(
+        &self,
+        audio_rx: Receiver<Vec<f32>>,
+        mut on_segment: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(Segment),
+    {
+        let script_code = get_script();
+
+        Python::attach(|py| {
+            let activators = PyModule::from_code(
+                py,
+                CString::new(script_code).unwrap().as_c_str(),
+                c_str!("whisper.py"),
+                c_str!("Whisper"),
+            )
+            .expect("should have activators");
+
+            let args = (
+                self.model.clone(),
+                self.device.clone(),
+                self.compute_type.clone(),
+            );
+            let model = activators.getattr("new_model")?.call1(args)?;
+
+            let vad = (
+                self.config.vad.active,
+                self.config.vad.threshold,
+                self.config.vad.min_speech_duration,
+                Self::convert(self.config.vad.max_speech_duration),
+                self.config.vad.min_silence_duration,
+                self.config.vad.padding_duration,
+            );
+
+            let mut buffer: Vec<f32> = Vec::new();
+            // Absolute time, in seconds, that sample 0 of `buffer` corresponds
+            // to — advances every time confirmed audio is trimmed off.
+            let mut buffer_offset = 0.0f32;
+            let mut since_last_parse: usize = 0;
+            let parse_interval_samples =
+                (self.config.parse_interval * SAMPLE_RATE as f32) as usize;
+            let mut previous_hypothesis: Vec<StreamWord> = Vec::new();
+            let mut next_id = 0i32;
+            // End time of the last committed word, in the current buffer's
+            // (post-trim) coordinates. `trim_buffer` keeps a lookback window
+            // before that word for decode context, so words at or before
+            // this cutoff in any later hypothesis are re-decoded lookback,
+            // not new content, and must not be re-emitted.
+            let mut committed_end_in_buffer = 0.0f32;
+
+            for chunk in &audio_rx {
+                buffer.extend_from_slice(&chunk);
+                since_last_parse += chunk.len();
+
+                if since_last_parse < parse_interval_samples {
+                    continue;
+                }
+                since_last_parse = 0;
+
+                let hypothesis =
+                    self.transcribe_words(&activators, &model, &vad, &buffer)?;
+                // The buffer still carries its trailing lookback into
+                // already-committed audio, so drop those words before
+                // comparing against `previous_hypothesis` (which only ever
+                // covers the uncommitted tail) — otherwise the lookback
+                // misaligns the comparison and can get committed again once
+                // it "stabilizes".
+                let hypothesis: Vec<StreamWord> = hypothesis
+                    .into_iter()
+                    .filter(|w| w.start >= committed_end_in_buffer)
+                    .collect();
+                let agreed_len = Self::longest_common_prefix(&previous_hypothesis, &hypothesis);
+
+                if agreed_len > 0 {
+                    let agreed = &hypothesis[..agreed_len];
+                    next_id += 1;
+                    on_segment(Self::words_to_segment(next_id, agreed, buffer_offset));
+
+                    let trim_seconds = Self::trim_buffer(
+                        &mut buffer,
+                        agreed[agreed_len - 1].end,
+                        self.config.buffer_trim_padding,
+                    );
+                    committed_end_in_buffer = agreed[agreed_len - 1].end - trim_seconds;
+                    buffer_offset += trim_seconds;
+                    previous_hypothesis = hypothesis[agreed_len..]
+                        .iter()
+                        .map(|w| StreamWord {
+                            word: w.word.clone(),
+                            start: w.start - trim_seconds,
+                            end: w.end - trim_seconds,
+                            probability: w.probability,
+                        })
+                        .collect();
+                } else {
+                    previous_hypothesis = hypothesis;
+                }
+            }
+
+            // Stream closed: commit whatever the last pass still held back,
+            // since no further audio is coming to confirm it further. The
+            // buffer still carries its trailing lookback into already
+            // committed audio, so drop words that fall at or before it
+            // instead of re-emitting them.
+            if !buffer.is_empty() {
+                let hypothesis =
+                    self.transcribe_words(&activators, &model, &vad, &buffer)?;
+                let final_words: Vec<StreamWord> = hypothesis
+                    .into_iter()
+                    .filter(|w| w.start >= committed_end_in_buffer)
+                    .collect();
+                if !final_words.is_empty() {
+                    next_id += 1;
+                    on_segment(Self::words_to_segment(
+                        next_id,
+                        &final_words,
+                        buffer_offset,
+                    ));
+                }
+            }
+
+            Ok::<(), Box<dyn Error>>(())
+        })
+    }
+
+    /// Re-transcribes `buffer` with word-level timestamps via the embedded
+    /// `transcribe_words` Python helper, using the same decode config
+    /// (`beam_size`, `best_of`, prompts, temperature fallback, ...) as
+    /// `transcribe`, so stream output doesn't diverge from a one-shot
+    /// transcription of the same audio.
+    fn transcribe_words(
+        &self,
+        activators: &Bound<'_, PyModule>,
+        model: &Bound<'_, pyo3::PyAny>,
+        vad: &(bool, f32, f32, String, f32, f32),
+        buffer: &[f32],
+    ) -> Result<Vec<StreamWord>, Box<dyn Error>> {
+        let wav_path = write_temp_wav(buffer)?;
+
+        let words = activators
+            .getattr("transcribe_words")?
+            .call1((
+                model,
+                wav_path.to_string_lossy().to_string(),
+                Self::convert(self.config.starting_prompt.clone()),
+                Self::convert(self.config.prefix.clone()),
+                Self::convert(self.config.language.clone()),
+                self.config.beam_size,
+                self.config.best_of,
+                self.config.patience,
+                self.config.length_penalty,
+                Self::convert(self.config.chunk_length.clone()),
+                vad.clone(),
+                (
+                    self.config.temperatures.clone(),
+                    self.config.compression_ratio_threshold,
+                    self.config.log_prob_threshold,
+                    self.config.no_speech_threshold,
+                ),
+            ))?
+            .extract::<Vec<(String, f32, f32, f32)>>();
+
+        let _ = std::fs::remove_file(&wav_path);
+
+        Ok(words?
+            .into_iter()
+            .map(|(word, start, end, probability)| StreamWord {
+                word,
+                start,
+                end,
+                probability,
+            })
+            .collect())
+    }
+
+    fn words_to_segment(id: i32, words: &[StreamWord], offset: f32) -> Segment {
+        Segment {
+            id,
+            seek: 0,
+            start: words.first().unwrap().start + offset,
+            end: words.last().unwrap().end + offset,
+            text: words.iter().map(|w| w.word.clone()).collect(),
+            temperature: 0.0,
+            avg_logprob: 0.0,
+            compression_ratio: 0.0,
+            no_speech_prob: 0.0,
+            words: words
+                .iter()
+                .map(|w| Word {
+                    start: w.start + offset,
+                    end: w.end + offset,
+                    word: w.word.clone(),
+                    probability: w.probability,
+                })
+                .collect(),
+        }
+    }
+
+    /// Drops the audio behind `committed_end` (minus `trim_padding` seconds
+    /// of lookback) from the front of `buffer`. Returns how many seconds were
+    /// trimmed, so callers can re-base any timestamps kept around.
+    fn trim_buffer(buffer: &mut Vec<f32>, committed_end: f32, trim_padding: f32) -> f32 {
+        let trim_seconds = (committed_end - trim_padding).max(0.0);
+        let trim_samples = ((trim_seconds * SAMPLE_RATE as f32) as usize).min(buffer.len());
+        buffer.drain(0..trim_samples);
+        trim_samples as f32 / SAMPLE_RATE as f32
+    }
+
+    /// Length of the longest prefix where `a` and `b` agree word-for-word —
+    /// the LocalAgreement-2 stability test.
+    fn longest_common_prefix(a: &[StreamWord], b: &[StreamWord]) -> usize {
+        a.iter()
+            .zip(b.iter())
+            .take_while(|(x, y)| x.word == y.word)
+            .count()
+    }
+
     fn convert<T: ToString>(value: Option<T>) -> String {
         match value {
             Some(x) => x.to_string(),
@@ -145,6 +917,83 @@ impl WhisperTranscriber {
     }
 }
 
+/// Writes `samples` (mono, expected to already be at `SAMPLE_RATE`) out as a
+/// 16-bit PCM WAV file faster-whisper can decode, for code paths whose audio
+/// isn't already backed by a file on disk.
+fn write_temp_wav(samples: &[f32]) -> Result<std::path::PathBuf, Box<dyn Error>> {
+    let mut path = std::env::temp_dir();
+    path.push(format!(
+        "faster-whisper-rs-{}-{}.wav",
+        std::process::id(),
+        samples.len()
+    ));
+
+    let mut file = std::fs::File::create(&path)?;
+    let data_len = (samples.len() * 2) as u32;
+    let byte_rate = SAMPLE_RATE * 2;
+
+    file.write_all(b"RIFF")?;
+    file.write_all(&(36 + data_len).to_le_bytes())?;
+    file.write_all(b"WAVE")?;
+    file.write_all(b"fmt ")?;
+    file.write_all(&16u32.to_le_bytes())?;
+    file.write_all(&1u16.to_le_bytes())?;
+    file.write_all(&1u16.to_le_bytes())?;
+    file.write_all(&SAMPLE_RATE.to_le_bytes())?;
+    file.write_all(&byte_rate.to_le_bytes())?;
+    file.write_all(&2u16.to_le_bytes())?;
+    file.write_all(&16u16.to_le_bytes())?;
+    file.write_all(b"data")?;
+    file.write_all(&data_len.to_le_bytes())?;
+
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        file.write_all(&value.to_le_bytes())?;
+    }
+
+    Ok(path)
+}
+
+/// Converts one row yielded by `pyscripts::transcribe_progress` into a
+/// `Segment`, shared by `WhisperTranscriber::transcribe_with_progress` and
+/// `WhisperModel::transcribe_with_progress`.
+fn progress_tuple_to_segment(
+    segment: (
+        i32,
+        i32,
+        f32,
+        f32,
+        String,
+        f32,
+        f32,
+        f32,
+        f32,
+        Vec<(f32, f32, String, f32)>,
+    ),
+) -> Segment {
+    Segment {
+        id: segment.0,
+        seek: segment.1,
+        start: segment.2,
+        end: segment.3,
+        text: segment.4,
+        temperature: segment.5,
+        avg_logprob: segment.6,
+        compression_ratio: segment.7,
+        no_speech_prob: segment.8,
+        words: segment
+            .9
+            .into_iter()
+            .map(|(start, end, word, probability)| Word {
+                start,
+                end,
+                word,
+                probability,
+            })
+            .collect(),
+    }
+}
+
 impl Default for WhisperModel {
     fn default() -> Self {
         return Self::new(
@@ -220,6 +1069,13 @@ impl WhisperModel {
                 self.config.length_penalty,
                 Self::convert(self.config.chunk_length.clone()),
                 vad,
+                self.config.word_timestamps,
+                (
+                    self.config.temperatures.clone(),
+                    self.config.compression_ratio_threshold,
+                    self.config.log_prob_threshold,
+                    self.config.no_speech_threshold,
+                ),
             );
 
             let pysegments = self
@@ -227,7 +1083,18 @@ impl WhisperModel {
                 .getattr(py, "transcribe_audio")
                 .unwrap()
                 .call1(py, args)?
-                .extract::<Vec<(i32, i32, f32, f32, String, f32, f32, f32, f32)>>(py)?;
+                .extract::<Vec<(
+                    i32,
+                    i32,
+                    f32,
+                    f32,
+                    String,
+                    f32,
+                    f32,
+                    f32,
+                    f32,
+                    Vec<(f32, f32, String, f32)>,
+                )>>(py)?;
 
             let mut segments = Vec::with_capacity(pysegments.len());
 
@@ -242,6 +1109,16 @@ impl WhisperModel {
                     avg_logprob: segment.6,
                     compression_ratio: segment.7,
                     no_speech_prob: segment.8,
+                    words: segment
+                        .9
+                        .into_iter()
+                        .map(|(start, end, word, probability)| Word {
+                            start,
+                            end,
+                            word,
+                            probability,
+                        })
+                        .collect(),
                 });
             }
 
@@ -256,6 +1133,72 @@ impl WhisperModel {
 
         return Ok(Segments(text, segments));
     }
+
+    /// Streams segments one at a time as faster-whisper decodes them,
+    /// instead of materializing the whole file's output before returning.
+    /// `on_segment` is invoked, with the GIL released, after each segment is
+    /// decoded; setting `cancel` stops pulling further segments from the
+    /// underlying generator, aborting the rest of the decode.
+    pub fn transcribe_with_progress<F>(
+        &self,
+        path: String,
+        cancel: Arc<AtomicBool>,
+        mut on_segment: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&Segment) + Send,
+    {
+        Python::with_gil(|py| {
+            let vad = (
+                self.config.vad.active,
+                self.config.vad.threshold,
+                self.config.vad.min_speech_duration,
+                Self::convert(self.config.vad.max_speech_duration),
+                self.config.vad.min_silence_duration,
+                self.config.vad.padding_duration,
+            );
+
+            let args = (
+                self.model.clone_ref(py),
+                path,
+                Self::convert(self.config.starting_prompt.clone()),
+                Self::convert(self.config.prefix.clone()),
+                Self::convert(self.config.language.clone()),
+                self.config.beam_size,
+                self.config.best_of,
+                self.config.patience,
+                self.config.length_penalty,
+                Self::convert(self.config.chunk_length.clone()),
+                vad,
+                self.config.word_timestamps,
+                (
+                    self.config.temperatures.clone(),
+                    self.config.compression_ratio_threshold,
+                    self.config.log_prob_threshold,
+                    self.config.no_speech_threshold,
+                ),
+            );
+
+            let generator = self
+                .module
+                .getattr(py, "transcribe_progress")
+                .unwrap()
+                .call1(py, args)?;
+            let generator = generator.bind(py);
+            let mut generator = generator.try_iter()?;
+
+            while !cancel.load(Ordering::Relaxed) {
+                let Some(item) = generator.next() else {
+                    break;
+                };
+
+                let segment = progress_tuple_to_segment(item?.extract()?);
+                py.allow_threads(|| on_segment(&segment));
+            }
+
+            Ok::<(), Box<dyn Error>>(())
+        })
+    }
 }
 
 #[test]
@@ -279,6 +1222,148 @@ fn transcriber_test() {
     assert!(!trans.0.is_empty());
 }
 
+#[test]
+fn resample_to_16k_no_op_at_target_rate() {
+    let samples = vec![0.1, -0.2, 0.3, -0.4];
+    let resampled = resample_to_16k(&samples, SAMPLE_RATE).unwrap();
+    assert_eq!(resampled, samples);
+}
+
+#[test]
+fn resample_to_16k_scales_length_by_rate_ratio() {
+    let samples = vec![0.0f32; SAMPLE_RATE as usize];
+    let resampled = resample_to_16k(&samples, 8_000).unwrap();
+    assert_eq!(resampled.len(), 2 * SAMPLE_RATE as usize);
+}
+
+#[test]
+fn format_timestamp_srt_uses_comma_separator() {
+    assert_eq!(format_timestamp_srt(3_661.234), "01:01:01,234");
+}
+
+#[test]
+fn format_timestamp_vtt_uses_dot_separator() {
+    assert_eq!(format_timestamp_vtt(3_661.234), "01:01:01.234");
+}
+
+#[test]
+fn format_timestamp_clamps_negative_seconds_to_zero() {
+    assert_eq!(format_timestamp(-5.0, ','), "00:00:00,000");
+}
+
+#[test]
+fn csv_escape_passes_plain_fields_through() {
+    assert_eq!(csv_escape("hello world"), "hello world");
+}
+
+#[test]
+fn csv_escape_quotes_and_doubles_embedded_quotes() {
+    assert_eq!(csv_escape("say \"hi\", ok"), "\"say \"\"hi\"\", ok\"");
+}
+
+#[test]
+fn json_escape_escapes_control_characters() {
+    assert_eq!(
+        json_escape("line\nwith\t\"quotes\"\\"),
+        "line\\nwith\\t\\\"quotes\\\"\\\\"
+    );
+}
+
+#[test]
+fn json_float_renders_non_finite_as_null() {
+    assert_eq!(json_float(f32::NAN), "null");
+    assert_eq!(json_float(f32::INFINITY), "null");
+    assert_eq!(json_float(0.5), "0.5");
+}
+
+#[test]
+fn csv_float_renders_non_finite_as_blank() {
+    assert_eq!(csv_float(f32::NAN), "");
+    assert_eq!(csv_float(f32::INFINITY), "");
+    assert_eq!(csv_float(0.5), "0.5");
+}
+
+fn sample_segments() -> Segments {
+    Segments(
+        String::new(),
+        vec![Segment {
+            id: 1,
+            seek: 0,
+            start: 0.0,
+            end: 1.5,
+            text: "hello, world".to_string(),
+            temperature: 0.0,
+            avg_logprob: -0.2,
+            compression_ratio: 1.1,
+            no_speech_prob: 0.01,
+            words: vec![Word {
+                start: 0.0,
+                end: 1.5,
+                word: "hello".to_string(),
+                probability: 0.9,
+            }],
+        }],
+    )
+}
+
+#[test]
+fn segments_to_srt_formats_index_and_timestamps() {
+    let srt = sample_segments().to_srt();
+    assert_eq!(srt, "1\n00:00:00,000 --> 00:00:01,500\nhello, world\n\n");
+}
+
+#[test]
+fn segments_to_vtt_emits_one_cue_per_word_when_present() {
+    let vtt = sample_segments().to_vtt();
+    assert_eq!(
+        vtt,
+        "WEBVTT\n\n00:00:00.000 --> 00:00:01.500\nhello\n\n"
+    );
+}
+
+#[test]
+fn segments_to_csv_escapes_comma_in_text() {
+    let csv = sample_segments().to_csv();
+    let data_row = csv.lines().nth(1).unwrap();
+    assert_eq!(
+        data_row,
+        "1,0,0,1.5,\"hello, world\",-0.2,1.1,0.01"
+    );
+}
+
+#[test]
+fn segments_to_json_includes_words() {
+    let json = sample_segments().to_json();
+    assert!(json.contains(r#""word":"hello""#));
+    assert!(json.contains(r#""text":"hello, world""#));
+}
+
+#[test]
+fn longest_common_prefix_stops_at_first_mismatch() {
+    let make = |words: &[&str]| -> Vec<StreamWord> {
+        words
+            .iter()
+            .map(|w| StreamWord {
+                word: w.to_string(),
+                start: 0.0,
+                end: 0.0,
+                probability: 1.0,
+            })
+            .collect()
+    };
+    let a = make(&["one", "two", "three"]);
+    let b = make(&["one", "two", "four"]);
+    assert_eq!(WhisperTranscriber::longest_common_prefix(&a, &b), 2);
+}
+
+#[test]
+fn trim_buffer_drops_confirmed_audio_keeping_padding() {
+    let mut buffer: Vec<f32> = vec![0.0; SAMPLE_RATE as usize];
+    let trimmed_seconds = WhisperTranscriber::trim_buffer(&mut buffer, 0.8, 0.1);
+    assert_eq!(trimmed_seconds, 0.7);
+    assert_eq!(buffer.len(), SAMPLE_RATE as usize - (0.7 * SAMPLE_RATE as f32) as usize);
+}
+
 pub fn get_path(path: String) -> String {
     let mut new_path = env!("CARGO_MANIFEST_DIR").to_string();
     new_path.push_str(&format!("/src/{}", path));
@@ -0,0 +1,307 @@
+/// Returns the embedded Python source that backs every `WhisperModel` /
+/// `WhisperTranscriber` call. Keeping it as a single in-process module
+/// (loaded via `PyModule::from_code`: once per `WhisperModel`, and on every
+/// call for `WhisperTranscriber`) avoids shipping a companion
+/// `.py` file that could drift out of sync with the crate version.
+pub fn get_script() -> String {
+    r#"
+from faster_whisper import WhisperModel
+
+
+def _opt(value):
+    return None if value == "None" else value
+
+
+def new_model(model, device, compute_type):
+    return WhisperModel(model, device=device, compute_type=compute_type)
+
+
+def transcribe_audio(
+    model,
+    path,
+    initial_prompt,
+    prefix,
+    language,
+    beam_size,
+    best_of,
+    patience,
+    length_penalty,
+    chunk_length,
+    vad,
+    word_timestamps,
+    temperature_fallback,
+):
+    active, threshold, min_speech_duration, max_speech_duration, min_silence_duration, padding_duration = vad
+    temperatures, compression_ratio_threshold, log_prob_threshold, no_speech_threshold = temperature_fallback
+
+    vad_parameters = None
+    if active:
+        vad_parameters = dict(
+            threshold=threshold,
+            min_speech_duration_ms=int(min_speech_duration * 1000),
+            max_speech_duration_s=float(_opt(max_speech_duration))
+            if _opt(max_speech_duration) is not None
+            else float("inf"),
+            min_silence_duration_ms=int(min_silence_duration * 1000),
+            speech_pad_ms=int(padding_duration * 1000),
+        )
+
+    segments, _info = model.transcribe(
+        path,
+        initial_prompt=_opt(initial_prompt),
+        prefix=_opt(prefix),
+        language=_opt(language),
+        beam_size=beam_size,
+        best_of=best_of,
+        patience=patience,
+        length_penalty=length_penalty,
+        chunk_length=int(_opt(chunk_length)) if _opt(chunk_length) is not None else None,
+        vad_filter=active,
+        vad_parameters=vad_parameters,
+        word_timestamps=word_timestamps,
+        temperature=tuple(temperatures),
+        compression_ratio_threshold=compression_ratio_threshold,
+        log_prob_threshold=log_prob_threshold,
+        no_speech_threshold=no_speech_threshold,
+    )
+
+    return [
+        (
+            segment.id,
+            segment.seek,
+            segment.start,
+            segment.end,
+            segment.text,
+            segment.temperature,
+            segment.avg_logprob,
+            segment.compression_ratio,
+            segment.no_speech_prob,
+            [
+                (word.start, word.end, word.word, word.probability)
+                for word in (segment.words or [])
+            ]
+            if word_timestamps
+            else [],
+        )
+        for segment in segments
+    ]
+
+
+def transcribe_progress(
+    model,
+    path,
+    initial_prompt,
+    prefix,
+    language,
+    beam_size,
+    best_of,
+    patience,
+    length_penalty,
+    chunk_length,
+    vad,
+    word_timestamps,
+    temperature_fallback,
+):
+    active, threshold, min_speech_duration, max_speech_duration, min_silence_duration, padding_duration = vad
+    temperatures, compression_ratio_threshold, log_prob_threshold, no_speech_threshold = temperature_fallback
+
+    vad_parameters = None
+    if active:
+        vad_parameters = dict(
+            threshold=threshold,
+            min_speech_duration_ms=int(min_speech_duration * 1000),
+            max_speech_duration_s=float(_opt(max_speech_duration))
+            if _opt(max_speech_duration) is not None
+            else float("inf"),
+            min_silence_duration_ms=int(min_silence_duration * 1000),
+            speech_pad_ms=int(padding_duration * 1000),
+        )
+
+    segments, _info = model.transcribe(
+        path,
+        initial_prompt=_opt(initial_prompt),
+        prefix=_opt(prefix),
+        language=_opt(language),
+        beam_size=beam_size,
+        best_of=best_of,
+        patience=patience,
+        length_penalty=length_penalty,
+        chunk_length=int(_opt(chunk_length)) if _opt(chunk_length) is not None else None,
+        vad_filter=active,
+        vad_parameters=vad_parameters,
+        word_timestamps=word_timestamps,
+        temperature=tuple(temperatures),
+        compression_ratio_threshold=compression_ratio_threshold,
+        log_prob_threshold=log_prob_threshold,
+        no_speech_threshold=no_speech_threshold,
+    )
+
+    # faster-whisper's `segments` is a lazy generator: decoding happens as it
+    # is pulled, not up front. Yielding here (instead of building a list like
+    # `transcribe_audio` does) lets the Rust side get each segment as soon as
+    # it's decoded, and stop pulling — which stops decoding — if cancelled.
+    for segment in segments:
+        yield (
+            segment.id,
+            segment.seek,
+            segment.start,
+            segment.end,
+            segment.text,
+            segment.temperature,
+            segment.avg_logprob,
+            segment.compression_ratio,
+            segment.no_speech_prob,
+            [
+                (word.start, word.end, word.word, word.probability)
+                for word in (segment.words or [])
+            ]
+            if word_timestamps
+            else [],
+        )
+
+
+def transcribe_batch(
+    model,
+    paths,
+    initial_prompt,
+    prefix,
+    language,
+    beam_size,
+    best_of,
+    patience,
+    length_penalty,
+    chunk_length,
+    vad,
+    word_timestamps,
+    temperature_fallback,
+    batch_size,
+):
+    active, threshold, min_speech_duration, max_speech_duration, min_silence_duration, padding_duration = vad
+    temperatures, compression_ratio_threshold, log_prob_threshold, no_speech_threshold = temperature_fallback
+
+    vad_parameters = None
+    if active:
+        vad_parameters = dict(
+            threshold=threshold,
+            min_speech_duration_ms=int(min_speech_duration * 1000),
+            max_speech_duration_s=float(_opt(max_speech_duration))
+            if _opt(max_speech_duration) is not None
+            else float("inf"),
+            min_silence_duration_ms=int(min_silence_duration * 1000),
+            speech_pad_ms=int(padding_duration * 1000),
+        )
+
+    try:
+        from faster_whisper import BatchedInferencePipeline
+
+        pipeline = BatchedInferencePipeline(model=model)
+    except ImportError:
+        pipeline = None
+
+    results = []
+    for path in paths:
+        decode_kwargs = dict(
+            initial_prompt=_opt(initial_prompt),
+            prefix=_opt(prefix),
+            language=_opt(language),
+            beam_size=beam_size,
+            best_of=best_of,
+            patience=patience,
+            length_penalty=length_penalty,
+            chunk_length=int(_opt(chunk_length)) if _opt(chunk_length) is not None else None,
+            vad_filter=active,
+            vad_parameters=vad_parameters,
+            word_timestamps=word_timestamps,
+            temperature=tuple(temperatures),
+            compression_ratio_threshold=compression_ratio_threshold,
+            log_prob_threshold=log_prob_threshold,
+            no_speech_threshold=no_speech_threshold,
+        )
+
+        if pipeline is not None:
+            segments, _info = pipeline.transcribe(path, batch_size=batch_size, **decode_kwargs)
+        else:
+            segments, _info = model.transcribe(path, **decode_kwargs)
+
+        results.append(
+            [
+                (
+                    segment.id,
+                    segment.seek,
+                    segment.start,
+                    segment.end,
+                    segment.text,
+                    segment.temperature,
+                    segment.avg_logprob,
+                    segment.compression_ratio,
+                    segment.no_speech_prob,
+                    [
+                        (word.start, word.end, word.word, word.probability)
+                        for word in (segment.words or [])
+                    ]
+                    if word_timestamps
+                    else [],
+                )
+                for segment in segments
+            ]
+        )
+
+    return results
+
+
+def transcribe_words(
+    model,
+    path,
+    initial_prompt,
+    prefix,
+    language,
+    beam_size,
+    best_of,
+    patience,
+    length_penalty,
+    chunk_length,
+    vad,
+    temperature_fallback,
+):
+    active, threshold, min_speech_duration, max_speech_duration, min_silence_duration, padding_duration = vad
+    temperatures, compression_ratio_threshold, log_prob_threshold, no_speech_threshold = temperature_fallback
+
+    vad_parameters = None
+    if active:
+        vad_parameters = dict(
+            threshold=threshold,
+            min_speech_duration_ms=int(min_speech_duration * 1000),
+            max_speech_duration_s=float(_opt(max_speech_duration))
+            if _opt(max_speech_duration) is not None
+            else float("inf"),
+            min_silence_duration_ms=int(min_silence_duration * 1000),
+            speech_pad_ms=int(padding_duration * 1000),
+        )
+
+    segments, _info = model.transcribe(
+        path,
+        initial_prompt=_opt(initial_prompt),
+        prefix=_opt(prefix),
+        language=_opt(language),
+        beam_size=beam_size,
+        best_of=best_of,
+        patience=patience,
+        length_penalty=length_penalty,
+        chunk_length=int(_opt(chunk_length)) if _opt(chunk_length) is not None else None,
+        vad_filter=active,
+        vad_parameters=vad_parameters,
+        word_timestamps=True,
+        temperature=tuple(temperatures),
+        compression_ratio_threshold=compression_ratio_threshold,
+        log_prob_threshold=log_prob_threshold,
+        no_speech_threshold=no_speech_threshold,
+    )
+
+    return [
+        (word.word, word.start, word.end, word.probability)
+        for segment in segments
+        for word in (segment.words or [])
+    ]
+"#
+    .to_string()
+}